@@ -14,11 +14,15 @@ use tracing::{debug, info};
 
 /// Creates a new window for a bar,
 /// sets it up and adds its widgets.
+///
+/// `config_pointer` is the JSON pointer to the bar's config
+/// inside the config file, used by the editor.
 pub fn create_bar(
     app: &Application,
     monitor: &Monitor,
     monitor_name: &str,
     config: Config,
+    config_pointer: &str,
 ) -> Result<()> {
     let win = ApplicationWindow::builder().application(app).build();
     let bar_name = config
@@ -29,6 +33,11 @@ pub fn create_bar(
     win.set_widget_name(&bar_name);
     info!("Creating bar {}", bar_name);
 
+    #[cfg(feature = "ipc")]
+    crate::editor::register_bar(&win, config_pointer);
+    #[cfg(not(feature = "ipc"))]
+    let _ = config_pointer;
+
     setup_layer_shell(
         &win,
         monitor,
@@ -230,6 +239,8 @@ fn add_modules(
             ModuleConfig::Script(mut module) => add_module!(module, id),
             #[cfg(feature = "sys_info")]
             ModuleConfig::SysInfo(mut module) => add_module!(module, id),
+            #[cfg(feature = "timers")]
+            ModuleConfig::Timers(mut module) => add_module!(module, id),
             #[cfg(feature = "tray")]
             ModuleConfig::Tray(mut module) => add_module!(module, id),
             #[cfg(feature = "upower")]
@@ -1,8 +1,10 @@
 use super::{Workspace, WorkspaceClient, WorkspaceUpdate};
 use crate::{arc_mut, lock, send};
-use color_eyre::Result;
-use hyprland::data::{Workspace as HWorkspace, Workspaces};
-use hyprland::dispatch::{Dispatch, DispatchType, WorkspaceIdentifierWithSpecial};
+use color_eyre::{Report, Result};
+use hyprland::data::{Clients, Workspace as HWorkspace, Workspaces};
+use hyprland::dispatch::{
+    Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial,
+};
 use hyprland::event_listener::EventListenerMutable as EventListener;
 use hyprland::prelude::*;
 use hyprland::shared::WorkspaceType;
@@ -227,6 +229,35 @@ impl WorkspaceClient for EventClient {
         Ok(())
     }
 
+    fn move_focused(&self, name: String) -> Result<()> {
+        Dispatch::call(DispatchType::MoveToWorkspaceSilent(
+            WorkspaceIdentifierWithSpecial::Name(&name),
+            None,
+        ))?;
+        Ok(())
+    }
+
+    fn rename(&self, name: String, new_name: String) -> Result<()> {
+        let id = Workspaces::get()?
+            .find_map(|w| if w.name == name { Some(w.id) } else { None })
+            .ok_or_else(|| Report::msg(format!("Unable to locate workspace '{name}'")))?;
+
+        Dispatch::call(DispatchType::RenameWorkspace(id, Some(&new_name)))?;
+        Ok(())
+    }
+
+    fn kill_all(&self, name: String) -> Result<()> {
+        let clients = Clients::get()?.filter(|client| client.workspace.name == name);
+
+        for client in clients {
+            Dispatch::call(DispatchType::CloseWindow(WindowIdentifier::Address(
+                client.address,
+            )))?;
+        }
+
+        Ok(())
+    }
+
     fn subscribe_workspace_change(&self) -> Receiver<WorkspaceUpdate> {
         let rx = self.workspace_tx.subscribe();
 
@@ -99,6 +99,16 @@ pub trait WorkspaceClient {
     /// Requests the workspace with this name is focused.
     fn focus(&self, name: String) -> Result<()>;
 
+    /// Requests the currently focused window is moved
+    /// to the workspace with this name.
+    fn move_focused(&self, name: String) -> Result<()>;
+
+    /// Requests the workspace with this name is renamed to `new_name`.
+    fn rename(&self, name: String, new_name: String) -> Result<()>;
+
+    /// Requests every window on the workspace with this name is closed.
+    fn kill_all(&self, name: String) -> Result<()>;
+
     /// Creates a new to workspace event receiver.
     fn subscribe_workspace_change(&self) -> broadcast::Receiver<WorkspaceUpdate>;
 }
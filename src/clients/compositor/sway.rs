@@ -58,6 +58,27 @@ impl WorkspaceClient for SwayEventClient {
         Ok(())
     }
 
+    fn move_focused(&self, name: String) -> color_eyre::Result<()> {
+        run_command(format!("move container to workspace {}", quote(&name)))
+    }
+
+    fn rename(&self, name: String, new_name: String) -> color_eyre::Result<()> {
+        run_command(format!(
+            "rename workspace {} to {}",
+            quote(&name),
+            quote(&new_name)
+        ))
+    }
+
+    fn kill_all(&self, name: String) -> color_eyre::Result<()> {
+        // criteria values are regexes,
+        // so the name must be escaped to only match this workspace.
+        let pattern = format!("^{}$", regex::escape(&name));
+        let pattern = pattern.replace('"', "\\\"");
+
+        run_command(format!("[workspace=\"{pattern}\"] kill"))
+    }
+
     fn subscribe_workspace_change(&self) -> Receiver<WorkspaceUpdate> {
         let rx = self.workspace_tx.subscribe();
 
@@ -98,6 +119,28 @@ async fn get_client() -> Arc<Mutex<Connection>> {
     Arc::clone(client)
 }
 
+/// Runs a command on the sway IPC client,
+/// returning an error if any of the command's outcomes failed.
+fn run_command(command: String) -> color_eyre::Result<()> {
+    let outcomes = await_sync(async move {
+        let client = get_client().await;
+        let mut client = client.lock().await;
+        client.run_command(command).await
+    })?;
+
+    for outcome in outcomes {
+        outcome?;
+    }
+
+    Ok(())
+}
+
+/// Wraps a command argument in double quotes,
+/// escaping any backslashes or quotes inside it.
+fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
 /// Gets the sway IPC event subscription client
 pub fn get_sub_client() -> &'static SwayEventClient {
     &SUB_CLIENT
@@ -15,12 +15,16 @@ use crate::modules::music::MusicModule;
 use crate::modules::script::ScriptModule;
 #[cfg(feature = "sys_info")]
 use crate::modules::sysinfo::SysInfoModule;
+#[cfg(feature = "timers")]
+use crate::modules::timers::TimersModule;
 #[cfg(feature = "tray")]
 use crate::modules::tray::TrayModule;
 #[cfg(feature = "upower")]
 use crate::modules::upower::UpowerModule;
 #[cfg(feature = "workspaces")]
 use crate::modules::workspaces::WorkspacesModule;
+#[cfg(feature = "recorder")]
+use crate::recorder::RecorderConfig;
 use cfg_if::cfg_if;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -44,6 +48,8 @@ pub enum ModuleConfig {
     Script(Box<ScriptModule>),
     #[cfg(feature = "sys_info")]
     SysInfo(Box<SysInfoModule>),
+    #[cfg(feature = "timers")]
+    Timers(Box<TimersModule>),
     #[cfg(feature = "tray")]
     Tray(Box<TrayModule>),
     #[cfg(feature = "upower")]
@@ -104,6 +110,10 @@ pub struct Config {
 
     pub ironvar_defaults: Option<HashMap<Box<str>, String>>,
 
+    /// Samples values to disk at an interval.
+    #[cfg(feature = "recorder")]
+    pub recorder: Option<RecorderConfig>,
+
     pub start: Option<Vec<ModuleConfig>>,
     pub center: Option<Vec<ModuleConfig>>,
     pub end: Option<Vec<ModuleConfig>>,
@@ -130,6 +140,8 @@ impl Default for Config {
             popup_gap: default_popup_gap(),
             icon_theme: None,
             ironvar_defaults: None,
+            #[cfg(feature = "recorder")]
+            recorder: None,
             start: Some(vec![ModuleConfig::Label(
                 LabelModule::new("ℹ️ Using default config".to_string()).into(),
             )]),
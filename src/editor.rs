@@ -0,0 +1,742 @@
+use crate::gtk_helpers::add_class;
+use crate::{arc_mut, lock};
+use color_eyre::eyre::WrapErr;
+use color_eyre::{Help, Report, Result};
+use gtk::gdk::{DragAction, ModifierType};
+use gtk::prelude::*;
+use gtk::{
+    Application, Button, DestDefaults, Entry, EventBox, Grid, Label, Orientation, TargetEntry,
+    TargetFlags, Widget, Window, WindowType,
+};
+use lazy_static::lazy_static;
+use serde_json::{Map, Value};
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tracing::{debug, info};
+use universal_config::ConfigLoader;
+
+/// GTK drag target used when reordering modules.
+const DRAG_TARGET: &str = "application/x-ironbar-module";
+
+/// Names of the bar containers,
+/// which match their config keys.
+const GROUPS: [&str; 3] = ["start", "center", "end"];
+
+lazy_static! {
+    static ref EDITOR: Arc<Mutex<Editor>> = arc_mut!(Editor::default());
+}
+
+// GTK widgets cannot be shared across threads,
+// so are kept separately to the global state.
+thread_local! {
+    /// Bar windows which can be edited,
+    /// alongside the JSON pointer of their config within the raw config.
+    static BARS: RefCell<Vec<(Window, String)>> = const { RefCell::new(Vec::new()) };
+
+    /// Modules which are currently wrapped for editing.
+    static MODULES: RefCell<Vec<EditorModule>> = const { RefCell::new(Vec::new()) };
+
+    /// Options windows which are currently open.
+    static OPTION_WINDOWS: RefCell<Vec<Window>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Global state for the bar editor.
+///
+/// While enabled, the raw config is held in memory
+/// and modified as modules are reordered or their options changed.
+/// It is written back to disk once the editor is disabled.
+#[derive(Debug, Default)]
+struct Editor {
+    enabled: bool,
+    dirty: bool,
+    /// Path of the config file the bars were loaded from.
+    path: Option<PathBuf>,
+    /// Modified time of the config file when it was loaded,
+    /// used to avoid overwriting changes made outside the editor.
+    modified: Option<SystemTime>,
+    raw: Value,
+}
+
+/// A module which is wrapped for editing.
+#[derive(Clone)]
+struct EditorModule {
+    wrapper: gtk::Box,
+    group: gtk::Box,
+    /// JSON pointer to the group's module array.
+    pointer: String,
+    /// Index of the module within the group's module array.
+    /// This is shared with the wrapper's signal handlers.
+    index: Rc<Cell<usize>>,
+}
+
+/// Registers a bar window against the location of its config,
+/// so its modules can be edited.
+pub fn register_bar<W: IsA<Window>>(window: &W, config_pointer: &str) {
+    let window = window.upcast_ref::<Window>().clone();
+    BARS.with(|bars| bars.borrow_mut().push((window, config_pointer.to_string())));
+}
+
+/// Resets the editor state,
+/// recording the path of the config file which was loaded.
+/// This should be called before creating bars.
+///
+/// If `config_path` is `None`, the config was found by `ConfigLoader`
+/// and its path is looked up in the config directory.
+pub fn reset(config_path: Option<PathBuf>) {
+    let config_path = config_path.or_else(|| {
+        find_config_path()
+            .map_err(|err| debug!("Edit mode unavailable: {err:?}"))
+            .ok()
+    });
+
+    let modified = config_path
+        .as_deref()
+        .and_then(|path| get_modified(path).ok());
+
+    close_option_windows();
+    MODULES.with(|modules| modules.borrow_mut().clear());
+    BARS.with(|bars| bars.borrow_mut().clear());
+
+    let mut editor = lock!(EDITOR);
+    *editor = Editor {
+        path: config_path,
+        modified,
+        ..Editor::default()
+    };
+}
+
+/// Toggles edit mode on all bars.
+///
+/// When disabling, any changes are written to the config file
+/// and the bars are reloaded.
+///
+/// Returns whether edit mode is now enabled.
+pub fn toggle(application: &Application) -> Result<bool> {
+    let enabled = lock!(EDITOR).enabled;
+
+    if enabled {
+        disable(application)?;
+    } else {
+        enable()?;
+    }
+
+    Ok(!enabled)
+}
+
+fn enable() -> Result<()> {
+    let (path, modified) = {
+        let editor = lock!(EDITOR);
+        (editor.path.clone(), editor.modified)
+    };
+
+    let path = path.ok_or_else(|| {
+        Report::msg("Unable to locate the loaded config file").suggestion(
+            "Ensure there is a single config file in your ironbar config directory, or set IRONBAR_CONFIG",
+        )
+    })?;
+
+    if get_modified(&path).ok() != modified {
+        return Err(Report::msg("Config file has changed since it was loaded")
+            .suggestion("Reload ironbar before entering edit mode"));
+    }
+
+    let raw: Value = ConfigLoader::load(&path).wrap_err("Failed to load config file")?;
+
+    // check the config can be written back before allowing any edits
+    serialize(&raw, &path)?;
+
+    {
+        let mut editor = lock!(EDITOR);
+        editor.enabled = true;
+        editor.dirty = false;
+        editor.raw = raw;
+    }
+
+    info!("Enabling edit mode");
+
+    let bars = BARS.with(|bars| bars.borrow().clone());
+
+    for (window, bar_pointer) in bars {
+        for (group_name, group) in get_groups(&window) {
+            let pointer = format!("{bar_pointer}/{group_name}");
+
+            // each configured module is added to the group as a single widget,
+            // so child indices match config indices until modules are moved.
+            for (index, module) in group.children().iter().enumerate() {
+                wrap_module(module, &group, &pointer, index);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn disable(application: &Application) -> Result<()> {
+    let (dirty, path, modified) = {
+        let editor = lock!(EDITOR);
+        (editor.dirty, editor.path.clone(), editor.modified)
+    };
+
+    // edit mode stays enabled if saving fails,
+    // so that changes are not lost.
+    if let Some(path) = path.filter(|_| dirty) {
+        if get_modified(&path).ok() != modified {
+            return Err(
+                Report::msg("Config file was changed on disk while in edit mode")
+                    .note("Your changes have not been saved")
+                    .suggestion("Reload ironbar to discard your changes"),
+            );
+        }
+
+        let contents = serialize(&lock!(EDITOR).raw, &path)?;
+        fs::write(&path, contents).wrap_err("Failed to write config file")?;
+        info!("Saved config to {}", path.display());
+    }
+
+    info!("Disabling edit mode");
+
+    close_option_windows();
+
+    for module in MODULES.with(RefCell::take) {
+        unwrap_module(&module);
+    }
+
+    {
+        let mut editor = lock!(EDITOR);
+        editor.enabled = false;
+        editor.dirty = false;
+        editor.raw = Value::Null;
+    }
+
+    if dirty {
+        info!("Closing existing bars");
+        for window in application.windows() {
+            window.close();
+        }
+
+        crate::load_interface(application);
+    }
+
+    Ok(())
+}
+
+/// Finds the config file in the directory searched by `ConfigLoader`.
+///
+/// This is only successful if exactly one `config.*` file exists,
+/// so the file cannot differ from the one which was loaded.
+fn find_config_path() -> Result<PathBuf> {
+    let dir = ConfigLoader::new("ironbar")
+        .config_dir()
+        .map_err(Report::new)?;
+
+    let paths = fs::read_dir(&dir)
+        .wrap_err("Failed to read config directory")?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.file_stem().map_or(false, |stem| stem == "config"))
+        .collect::<Vec<_>>();
+
+    match paths.as_slice() {
+        [path] => Ok(path.clone()),
+        [] => Err(Report::msg("No config file found")),
+        _ => Err(Report::msg("Multiple config files found")),
+    }
+}
+
+/// Gets the last modified time of a file.
+fn get_modified(path: &Path) -> Result<SystemTime> {
+    Ok(fs::metadata(path)?.modified()?)
+}
+
+/// Serializes the raw config into the format
+/// matching the extension of the config file.
+fn serialize(raw: &Value, path: &Path) -> Result<String> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => Ok(serde_json::to_string_pretty(raw)?),
+        #[cfg(feature = "config+yaml")]
+        Some("yaml") => Ok(serde_yaml::to_string(raw)?),
+        #[cfg(feature = "config+toml")]
+        Some("toml") => Ok(toml::to_string_pretty(raw)?),
+        _ => Err(
+            Report::msg("Edit mode does not support saving this config format")
+                .suggestion("Edit mode supports JSON, YAML and TOML config files"),
+        ),
+    }
+}
+
+/// Gets each of the start/center/end containers on a bar window,
+/// alongside their names.
+fn get_groups(window: &Window) -> Vec<(&'static str, gtk::Box)> {
+    let content = window
+        .child()
+        .and_then(|child| child.downcast::<gtk::Box>().ok());
+
+    let Some(content) = content else { return vec![] };
+
+    content
+        .children()
+        .into_iter()
+        .filter_map(|child| {
+            let name = child.widget_name();
+            let group_name = GROUPS.into_iter().find(|group| name.as_str() == *group)?;
+            child
+                .downcast::<gtk::Box>()
+                .ok()
+                .map(|group| (group_name, group))
+        })
+        .collect()
+}
+
+/// Gets the index of `child` inside `container`.
+fn child_index<W: IsA<Widget>>(container: &gtk::Box, child: &W) -> Option<usize> {
+    container
+        .children()
+        .iter()
+        .position(|widget| widget == child.upcast_ref::<Widget>())
+}
+
+/// Moves a module inside the group at `from` to `to`
+/// inside the raw config.
+///
+/// Returns whether the module was moved.
+fn move_module(pointer: &str, from: usize, to: usize) -> bool {
+    let mut editor = lock!(EDITOR);
+    let editor = &mut *editor;
+
+    let moved = editor
+        .raw
+        .pointer_mut(pointer)
+        .map_or(false, |modules| move_item(modules, from, to));
+
+    if moved {
+        editor.dirty = true;
+    }
+
+    moved
+}
+
+/// Moves the item at `from` to `to` in a JSON array.
+///
+/// Returns whether the item was moved.
+fn move_item(array: &mut Value, from: usize, to: usize) -> bool {
+    match array {
+        Value::Array(items) if from != to && from < items.len() && to < items.len() => {
+            let item = items.remove(from);
+            items.insert(to, item);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Gets the new index of the item at `index`
+/// once the item at `from` has been moved to `to` by `move_item`.
+fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < to && (from..=to).contains(&index) {
+        index - 1
+    } else if to < from && (to..from).contains(&index) {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Updates the modules for the group array at `pointer`
+/// once the module at `from` has been moved to `to`.
+///
+/// Modules are reordered on every bar sharing the array,
+/// so that each bar matches the config.
+fn reorder_modules(pointer: &str, from: usize, to: usize) {
+    let mut modules = MODULES.with(|modules| {
+        modules
+            .borrow()
+            .iter()
+            .filter(|module| module.pointer == pointer)
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    for module in &modules {
+        module.index.set(moved_index(module.index.get(), from, to));
+    }
+
+    // placing each module in ascending order
+    // leaves every earlier module in its correct position
+    modules.sort_by_key(|module| module.index.get());
+
+    for module in modules {
+        module
+            .group
+            .reorder_child(&module.wrapper, module.index.get() as i32);
+    }
+}
+
+/// Creates the drag payload for the module at `index`
+/// in the group array at `pointer`.
+fn encode_payload(pointer: &str, index: usize) -> String {
+    format!("{pointer}/{index}")
+}
+
+/// Gets the module index from a drag payload,
+/// if it was dragged from the group array at `pointer`.
+fn decode_payload(payload: &str, pointer: &str) -> Option<usize> {
+    payload
+        .strip_prefix(pointer)?
+        .strip_prefix('/')?
+        .parse()
+        .ok()
+}
+
+/// Wraps a module in a container alongside a drag handle and options button.
+///
+/// `index` is the module's index within the group array at `pointer`.
+fn wrap_module(module: &Widget, group: &gtk::Box, pointer: &str, index: usize) {
+    let wrapper = gtk::Box::new(group.orientation(), 0);
+    add_class(&wrapper, "editor-module");
+
+    let handle = EventBox::new();
+    handle.add(&Label::new(Some("⠿")));
+    add_class(&handle, "editor-handle");
+
+    let options = Button::with_label("⚙");
+    add_class(&options, "editor-options");
+
+    group.remove(module);
+    wrapper.add(&handle);
+    wrapper.add(module);
+    wrapper.add(&options);
+    group.add(&wrapper);
+    group.reorder_child(&wrapper, index as i32);
+
+    let index = Rc::new(Cell::new(index));
+
+    let targets = [TargetEntry::new(DRAG_TARGET, TargetFlags::SAME_APP, 0)];
+
+    handle.drag_source_set(ModifierType::BUTTON1_MASK, &targets, DragAction::MOVE);
+    wrapper.drag_dest_set(DestDefaults::ALL, &targets, DragAction::MOVE);
+
+    {
+        let pointer = pointer.to_string();
+        let index = index.clone();
+
+        handle.connect_drag_data_get(move |_, _, data, _, _| {
+            let payload = encode_payload(&pointer, index.get());
+            data.set(&data.target(), 8, payload.as_bytes());
+        });
+    }
+
+    {
+        let pointer = pointer.to_string();
+        let index = index.clone();
+
+        wrapper.connect_drag_data_received(move |_, _, _, _, data, _, _| {
+            let payload = data.data();
+            let payload = String::from_utf8_lossy(&payload);
+
+            let Some(from) = decode_payload(&payload, &pointer) else {
+                debug!("Ignoring drop from another group: '{payload}'");
+                return;
+            };
+
+            let to = index.get();
+
+            if move_module(&pointer, from, to) {
+                reorder_modules(&pointer, from, to);
+            }
+        });
+    }
+
+    {
+        let pointer = pointer.to_string();
+        let index = index.clone();
+
+        options.connect_clicked(move |_| open_options(pointer.clone(), index.clone()));
+    }
+
+    // avoid `show_all` so hidden modules stay hidden
+    handle.show_all();
+    options.show();
+    wrapper.show();
+
+    MODULES.with(|modules| {
+        modules.borrow_mut().push(EditorModule {
+            wrapper,
+            group: group.clone(),
+            pointer: pointer.to_string(),
+            index,
+        });
+    });
+}
+
+/// Removes the editor wrapper from a module,
+/// returning the module to the wrapper's position in the group.
+fn unwrap_module(module: &EditorModule) {
+    let EditorModule { wrapper, group, .. } = module;
+
+    let Some(index) = child_index(group, wrapper) else { return };
+
+    // children are: [handle, module, options]
+    let Some(widget) = wrapper.children().get(1).cloned() else { return };
+
+    wrapper.remove(&widget);
+    group.remove(wrapper);
+    group.add(&widget);
+    group.reorder_child(&widget, index as i32);
+}
+
+/// Converts a scalar JSON value to a string for editing.
+/// Returns `None` for arrays and objects, which cannot be edited.
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(bool) => Some(bool.to_string()),
+        Value::Null => Some(String::new()),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// Parses an edited string back into a JSON value.
+///
+/// Strings remain strings, and an empty `null` remains `null`.
+/// Anything else is parsed as JSON,
+/// falling back to a string if it is not valid.
+fn parse_value(original: Option<&Value>, text: &str) -> Value {
+    match original {
+        Some(Value::String(_)) => Value::String(text.to_string()),
+        Some(Value::Null) if text.is_empty() => Value::Null,
+        _ => serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string())),
+    }
+}
+
+/// Opens a window to edit the options of the module at `index`
+/// in the group array at `pointer`.
+///
+/// Only scalar options can be edited.
+/// A new option can also be added.
+fn open_options(pointer: String, index: Rc<Cell<usize>>) {
+    let module = lock!(EDITOR)
+        .raw
+        .pointer(&format!("{pointer}/{}", index.get()))
+        .cloned();
+    let Some(Value::Object(module)) = module else { return };
+
+    let module_type = module
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("module");
+
+    let window = Window::builder()
+        .type_(WindowType::Toplevel)
+        .title(&format!("Edit {module_type}"))
+        .build();
+    add_class(&window, "editor-popup");
+
+    let container = gtk::Box::new(Orientation::Vertical, 10);
+    let grid = Grid::builder().row_spacing(5).column_spacing(10).build();
+
+    let mut entries = vec![];
+
+    let options = module
+        .iter()
+        .filter(|(key, _)| key.as_str() != "type")
+        .filter_map(|(key, value)| value_to_string(value).map(|value| (key, value)));
+
+    for (row, (key, value)) in options.enumerate() {
+        let label = Label::new(Some(key.as_str()));
+        label.set_halign(gtk::Align::Start);
+
+        let entry = Entry::new();
+        entry.set_text(&value);
+
+        grid.attach(&label, 0, row as i32, 1, 1);
+        grid.attach(&entry, 1, row as i32, 1, 1);
+
+        entries.push((key.clone(), value, entry));
+    }
+
+    let new_key = Entry::builder().placeholder_text("New option").build();
+    let new_value = Entry::builder().placeholder_text("Value").build();
+
+    let row = entries.len() as i32;
+    grid.attach(&new_key, 0, row, 1, 1);
+    grid.attach(&new_value, 1, row, 1, 1);
+
+    let buttons = gtk::Box::new(Orientation::Horizontal, 5);
+    buttons.set_halign(gtk::Align::End);
+
+    let cancel = Button::with_label("Cancel");
+    let apply = Button::with_label("Apply");
+
+    buttons.add(&cancel);
+    buttons.add(&apply);
+
+    container.add(&grid);
+    container.add(&buttons);
+    window.add(&container);
+
+    {
+        let window = window.clone();
+        cancel.connect_clicked(move |_| window.close());
+    }
+
+    {
+        let window = window.clone();
+
+        apply.connect_clicked(move |_| {
+            let mut changes = Map::new();
+
+            // only write back options which were changed,
+            // so untouched values keep their original type
+            for (key, original, entry) in &entries {
+                let text = entry.text();
+                if text.as_str() != original.as_str() {
+                    changes.insert(key.clone(), parse_value(module.get(key), &text));
+                }
+            }
+
+            let key = new_key.text();
+            if !key.is_empty() {
+                changes.insert(key.to_string(), parse_value(None, &new_value.text()));
+            }
+
+            if !changes.is_empty() {
+                let mut editor = lock!(EDITOR);
+                let editor = &mut *editor;
+
+                // the module may have been moved since the window was opened,
+                // so is located using its current index
+                let module_pointer = format!("{pointer}/{}", index.get());
+
+                if let (true, Some(Value::Object(module))) =
+                    (editor.enabled, editor.raw.pointer_mut(&module_pointer))
+                {
+                    module.extend(changes);
+                    editor.dirty = true;
+                }
+            }
+
+            window.close();
+        });
+    }
+
+    window.connect_destroy(|window| {
+        OPTION_WINDOWS.with(|windows| windows.borrow_mut().retain(|open| open != window));
+    });
+
+    OPTION_WINDOWS.with(|windows| windows.borrow_mut().push(window.clone()));
+
+    window.show_all();
+}
+
+/// Closes any open options windows.
+fn close_option_windows() {
+    for window in OPTION_WINDOWS.with(RefCell::take) {
+        window.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_value_to_string() {
+        assert_eq!(
+            value_to_string(&json!("hello")),
+            Some(String::from("hello"))
+        );
+        assert_eq!(value_to_string(&json!(5)), Some(String::from("5")));
+        assert_eq!(value_to_string(&json!(1.5)), Some(String::from("1.5")));
+        assert_eq!(value_to_string(&json!(true)), Some(String::from("true")));
+        assert_eq!(value_to_string(&Value::Null), Some(String::new()));
+        assert_eq!(value_to_string(&json!([1, 2])), None);
+        assert_eq!(value_to_string(&json!({ "a": 1 })), None);
+    }
+
+    #[test]
+    fn test_parse_value_keeps_type() {
+        assert_eq!(parse_value(Some(&json!("a")), "5"), json!("5"));
+        assert_eq!(parse_value(Some(&json!(1)), "5"), json!(5));
+        assert_eq!(parse_value(Some(&json!(false)), "true"), json!(true));
+    }
+
+    #[test]
+    fn test_parse_value_null() {
+        assert_eq!(parse_value(Some(&Value::Null), ""), Value::Null);
+        assert_eq!(parse_value(Some(&Value::Null), "10"), json!(10));
+    }
+
+    #[test]
+    fn test_parse_value_new() {
+        assert_eq!(parse_value(None, "10"), json!(10));
+        assert_eq!(parse_value(None, "false"), json!(false));
+        assert_eq!(parse_value(None, "{title}"), json!("{title}"));
+        assert_eq!(parse_value(None, ""), json!(""));
+    }
+
+    #[test]
+    fn test_move_item() {
+        let mut modules = json!(["a", "b", "c"]);
+
+        assert!(move_item(&mut modules, 0, 2));
+        assert_eq!(modules, json!(["b", "c", "a"]));
+
+        assert!(move_item(&mut modules, 2, 0));
+        assert_eq!(modules, json!(["a", "b", "c"]));
+    }
+
+    #[test]
+    fn test_move_item_invalid() {
+        let mut modules = json!(["a", "b"]);
+
+        assert!(!move_item(&mut modules, 0, 0));
+        assert!(!move_item(&mut modules, 0, 2));
+        assert!(!move_item(&mut modules, 2, 0));
+        assert_eq!(modules, json!(["a", "b"]));
+
+        assert!(!move_item(&mut json!({ "a": 1 }), 0, 1));
+    }
+
+    #[test]
+    fn test_moved_index() {
+        for (from, to) in [(0, 3), (3, 0), (1, 2), (2, 1), (0, 0)] {
+            let mut items = json!([0, 1, 2, 3]);
+            move_item(&mut items, from, to);
+
+            for index in 0..4 {
+                assert_eq!(
+                    items[moved_index(index, from, to)],
+                    json!(index),
+                    "moving {from} to {to}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_payload() {
+        let payload = encode_payload("/monitors/DP-1/0/start", 2);
+        assert_eq!(payload, "/monitors/DP-1/0/start/2");
+
+        assert_eq!(decode_payload(&payload, "/monitors/DP-1/0/start"), Some(2));
+        assert_eq!(decode_payload(&encode_payload("/end", 0), "/end"), Some(0));
+    }
+
+    #[test]
+    fn test_payload_other_group() {
+        let payload = encode_payload("/monitors/DP-1/0/start", 2);
+
+        assert_eq!(decode_payload(&payload, "/monitors/DP-1/0/end"), None);
+        assert_eq!(decode_payload(&payload, "/monitors/DP-1/1/start"), None);
+        assert_eq!(decode_payload(&payload, "/monitors/DP-1/0"), None);
+        assert_eq!(decode_payload("", "/start"), None);
+        assert_eq!(decode_payload("/start/x", "/start"), None);
+    }
+}
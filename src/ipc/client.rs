@@ -19,10 +19,12 @@ impl Ipc {
         let write_buffer = serde_json::to_vec(&command)?;
         stream.write_all(&write_buffer).await?;
 
-        let mut read_buffer = vec![0; 1024];
-        let bytes = stream.read(&mut read_buffer).await?;
+        // the server closes the stream once the response is written,
+        // so read until the end to support responses larger than a single buffer.
+        let mut read_buffer = vec![];
+        stream.read_to_end(&mut read_buffer).await?;
 
-        let response = serde_json::from_slice(&read_buffer[..bytes])?;
+        let response = serde_json::from_slice(&read_buffer)?;
         Ok(response)
     }
 }
@@ -51,4 +51,17 @@ pub enum Command {
         /// Bar name to target.
         bar_name: String,
     },
+
+    /// Toggle edit mode on all bars.
+    /// While enabled, modules can be reordered and their options changed.
+    /// Changes are written to the config file when edit mode is toggled off.
+    ToggleEdit,
+
+    /// Get the most recent samples taken by the recorder, as a JSON array.
+    #[cfg(feature = "recorder")]
+    GetSamples {
+        /// Maximum number of samples to return.
+        /// Returns all samples held in memory if not set.
+        limit: Option<usize>,
+    },
 }
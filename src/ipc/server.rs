@@ -173,6 +173,20 @@ impl Ipc {
                     Response::error("Bar not found")
                 }
             }
+            Command::ToggleEdit => match crate::editor::toggle(application) {
+                Ok(enabled) => Response::OkValue {
+                    value: enabled.to_string(),
+                },
+                Err(err) => Response::error(&format!("{err}")),
+            },
+            #[cfg(feature = "recorder")]
+            Command::GetSamples { limit } => {
+                let recorder = crate::recorder::get_recorder();
+                let samples = read_lock!(recorder).recent_samples(limit);
+                Response::OkValue {
+                    value: samples.to_string(),
+                }
+            }
         }
     }
 
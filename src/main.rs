@@ -8,6 +8,8 @@ mod clients;
 mod config;
 mod desktop_file;
 mod dynamic_value;
+#[cfg(feature = "ipc")]
+mod editor;
 mod error;
 mod gtk_helpers;
 mod image;
@@ -19,6 +21,8 @@ mod logging;
 mod macros;
 mod modules;
 mod popup;
+#[cfg(feature = "recorder")]
+mod recorder;
 mod script;
 mod style;
 mod unique_id;
@@ -159,9 +163,12 @@ pub fn load_interface(app: &Application) {
         |display| display,
     );
 
-    let mut config = env::var("IRONBAR_CONFIG")
+    let config_path = env::var("IRONBAR_CONFIG").ok().map(PathBuf::from);
+
+    let mut config = config_path
+        .as_ref()
         .map_or_else(
-            |_| ConfigLoader::new("ironbar").find_and_load(),
+            || ConfigLoader::new("ironbar").find_and_load(),
             ConfigLoader::load,
         )
         .unwrap_or_else(|err| {
@@ -185,6 +192,19 @@ pub fn load_interface(app: &Application) {
         }
     }
 
+    #[cfg(feature = "recorder")]
+    {
+        let recorder = recorder::get_recorder();
+        let mut recorder = write_lock!(recorder);
+        match config.recorder.take() {
+            Some(recorder_config) => recorder.start(recorder_config),
+            None => recorder.stop(),
+        }
+    }
+
+    #[cfg(feature = "ipc")]
+    editor::reset(config_path);
+
     if let Err(err) = create_bars(app, &display, &config) {
         error!("{:?}", err);
         exit(ExitCode::CreateBars as i32);
@@ -213,22 +233,35 @@ fn create_bars(app: &Application, display: &Display, config: &Config) -> Result<
 
         let Some(monitor_name) = &output.name else { continue };
 
+        // JSON pointer to the bar config, used by the editor.
+        // `~` and `/` must be escaped in pointer tokens.
+        let pointer = format!(
+            "/monitors/{}",
+            monitor_name.replace('~', "~0").replace('/', "~1")
+        );
+
         config.monitors.as_ref().map_or_else(
             || {
                 info!("Creating bar on '{}'", monitor_name);
-                create_bar(app, &monitor, monitor_name, config.clone())
+                create_bar(app, &monitor, monitor_name, config.clone(), "")
             },
             |config| {
                 let config = config.get(monitor_name);
                 match &config {
                     Some(MonitorConfig::Single(config)) => {
                         info!("Creating bar on '{}'", monitor_name);
-                        create_bar(app, &monitor, monitor_name, config.clone())
+                        create_bar(app, &monitor, monitor_name, config.clone(), &pointer)
                     }
                     Some(MonitorConfig::Multiple(configs)) => {
-                        for config in configs {
+                        for (index, config) in configs.iter().enumerate() {
                             info!("Creating bar on '{}'", monitor_name);
-                            create_bar(app, &monitor, monitor_name, config.clone())?;
+                            create_bar(
+                                app,
+                                &monitor,
+                                monitor_name,
+                                config.clone(),
+                                &format!("{pointer}/{index}"),
+                            )?;
                         }
 
                         Ok(())
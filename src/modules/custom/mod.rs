@@ -10,17 +10,23 @@ use self::label::LabelWidget;
 use self::r#box::BoxWidget;
 use self::slider::SliderWidget;
 use crate::config::CommonConfig;
+#[cfg(feature = "ipc")]
+use crate::ironvar::get_variable_manager;
 use crate::modules::custom::button::ButtonWidget;
 use crate::modules::custom::progress::ProgressWidget;
 use crate::modules::{
     wrap_widget, Module, ModuleInfo, ModuleUpdateEvent, ModuleWidget, WidgetContext,
 };
-use crate::popup::WidgetGeometry;
+use crate::popup::{Popup, WidgetGeometry};
 use crate::script::Script;
-use crate::send_async;
+#[cfg(feature = "ipc")]
+use crate::write_lock;
+use crate::{send_async, try_send};
 use color_eyre::{Report, Result};
+use glib::IsA;
+use gtk::gdk::DragAction;
 use gtk::prelude::*;
-use gtk::{IconTheme, Orientation};
+use gtk::{DestDefaults, IconTheme, Orientation, TargetEntry, TargetFlags};
 use serde::Deserialize;
 use tokio::spawn;
 use tokio::sync::mpsc::{Receiver, Sender};
@@ -41,6 +47,9 @@ pub struct CustomModule {
 pub struct WidgetConfig {
     #[serde(flatten)]
     widget: Widget,
+    /// Command to run when text or files are dropped onto the widget.
+    /// The dropped payload is passed as arguments.
+    on_drop: Option<String>,
     #[serde(flatten)]
     common: CommonConfig,
 }
@@ -102,6 +111,69 @@ pub fn set_length<W: WidgetExt>(widget: &W, length: i32, bar_orientation: Orient
     };
 }
 
+/// Sets the widget up as a drag-and-drop target,
+/// sending `cmd` to the controller with the dropped payload as its args.
+///
+/// Dropped files are passed as one path per argument.
+/// Dropped text is passed as a single argument.
+fn install_drop_target<W: IsA<gtk::Widget>>(widget: &W, cmd: String, context: CustomWidgetContext) {
+    let targets = [
+        TargetEntry::new("text/uri-list", TargetFlags::empty(), 0),
+        TargetEntry::new("UTF8_STRING", TargetFlags::empty(), 1),
+        TargetEntry::new("text/plain", TargetFlags::empty(), 2),
+    ];
+
+    widget.drag_dest_set(DestDefaults::ALL, &targets, DragAction::COPY);
+
+    let tx = context.tx.clone();
+    let bar_orientation = context.bar_orientation;
+
+    widget.connect_drag_data_received(move |widget, _, _, _, data, _, _| {
+        let uris = data
+            .uris()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let text = data.text();
+
+        if let Some(args) = get_drop_args(&uris, text.as_deref()) {
+            debug!("Received drop with payload: {args:?}");
+
+            try_send!(
+                tx,
+                ExecEvent {
+                    cmd: cmd.clone(),
+                    args: Some(args),
+                    geometry: Popup::widget_geometry(widget, bar_orientation),
+                }
+            );
+        }
+    });
+}
+
+/// Converts a drop payload into command arguments.
+///
+/// Local file URIs are converted to paths,
+/// and any other URIs are passed through as-is.
+/// If no URIs were dropped, the text is used as a single argument.
+fn get_drop_args(uris: &[String], text: Option<&str>) -> Option<Vec<String>> {
+    if uris.is_empty() {
+        text.map(|text| vec![text.to_string()])
+    } else {
+        Some(
+            uris.iter()
+                .map(|uri| {
+                    glib::filename_from_uri(uri).map_or_else(
+                        |_| uri.clone(),
+                        |(path, _)| path.to_string_lossy().to_string(),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
 /// Attempts to parse an `Orientation` from `String`.
 /// Will accept `horizontal`, `vertical`, `h` or `v`.
 /// Ignores case.
@@ -113,9 +185,11 @@ fn try_get_orientation(orientation: &str) -> Result<Orientation> {
     }
 }
 
-impl Widget {
+impl WidgetConfig {
     /// Creates this widget and adds it to the parent container
-    fn add_to(self, parent: &gtk::Box, context: CustomWidgetContext, common: CommonConfig) {
+    fn add_to(self, parent: &gtk::Box, context: CustomWidgetContext) {
+        let common = self.common;
+
         macro_rules! create {
             ($widget:expr) => {
                 wrap_widget(
@@ -126,15 +200,19 @@ impl Widget {
             };
         }
 
-        let event_box = match self {
-            Self::Box(widget) => create!(widget),
-            Self::Label(widget) => create!(widget),
-            Self::Button(widget) => create!(widget),
-            Self::Image(widget) => create!(widget),
-            Self::Slider(widget) => create!(widget),
-            Self::Progress(widget) => create!(widget),
+        let event_box = match self.widget {
+            Widget::Box(widget) => create!(widget),
+            Widget::Label(widget) => create!(widget),
+            Widget::Button(widget) => create!(widget),
+            Widget::Image(widget) => create!(widget),
+            Widget::Slider(widget) => create!(widget),
+            Widget::Progress(widget) => create!(widget),
         };
 
+        if let Some(cmd) = self.on_drop {
+            install_drop_target(&event_box, cmd, context);
+        }
+
         parent.add(&event_box);
     }
 }
@@ -146,6 +224,23 @@ pub struct ExecEvent {
     geometry: WidgetGeometry,
 }
 
+/// Sets the ironvar with `key` to the provided args,
+/// joined by newlines.
+#[cfg(feature = "ipc")]
+fn set_ironvar(key: &str, args: Vec<String>) {
+    let variable_manager = get_variable_manager();
+    let mut variable_manager = write_lock!(variable_manager);
+
+    if let Err(err) = variable_manager.set(key.into(), args.join("\n")) {
+        error!("Failed to set ironvar '{key}': {err}");
+    }
+}
+
+#[cfg(not(feature = "ipc"))]
+fn set_ironvar(key: &str, _args: Vec<String>) {
+    error!("Unable to set ironvar '{key}': ironbar was compiled without the `ipc` feature");
+}
+
 impl Module<gtk::Box> for CustomModule {
     type SendMessage = ();
     type ReceiveMessage = ExecEvent;
@@ -172,6 +267,14 @@ impl Module<gtk::Box> for CustomModule {
                     if let Err(err) = script.get_output(Some(&args)).await {
                         error!("{err:?}");
                     }
+                } else if let Some(key) = event.cmd.strip_prefix("ironvar:") {
+                    match event.args {
+                        Some(args) => set_ironvar(key, args),
+                        None => error!(
+                            "Unable to set ironvar '{key}': command has no value. \
+                            `ironvar:<key>` can only be used with `on_drop` or `on_change`"
+                        ),
+                    }
                 } else if event.cmd == "popup:toggle" {
                     send_async!(tx, ModuleUpdateEvent::TogglePopup(event.geometry));
                 } else if event.cmd == "popup:open" {
@@ -202,9 +305,7 @@ impl Module<gtk::Box> for CustomModule {
         };
 
         self.bar.clone().into_iter().for_each(|widget| {
-            widget
-                .widget
-                .add_to(&container, custom_context, widget.common);
+            widget.add_to(&container, custom_context);
         });
 
         let popup = self.into_popup(context.controller_tx, context.popup_rx, info);
@@ -234,9 +335,7 @@ impl Module<gtk::Box> for CustomModule {
             };
 
             for widget in popup {
-                widget
-                    .widget
-                    .add_to(&container, custom_context, widget.common);
+                widget.add_to(&container, custom_context);
             }
         }
 
@@ -245,3 +344,47 @@ impl Module<gtk::Box> for CustomModule {
         Some(container)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_args_files() {
+        let uris = vec![
+            String::from("file:///home/user/file.txt"),
+            String::from("file:///home/user/with%20space.png"),
+        ];
+
+        assert_eq!(
+            get_drop_args(&uris, None),
+            Some(vec![
+                String::from("/home/user/file.txt"),
+                String::from("/home/user/with space.png")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_drop_args_remote_uri() {
+        let uris = vec![String::from("https://example.com/page")];
+
+        assert_eq!(
+            get_drop_args(&uris, Some("ignored")),
+            Some(vec![String::from("https://example.com/page")])
+        );
+    }
+
+    #[test]
+    fn test_drop_args_text() {
+        assert_eq!(
+            get_drop_args(&[], Some("hello world")),
+            Some(vec![String::from("hello world")])
+        );
+    }
+
+    #[test]
+    fn test_drop_args_empty() {
+        assert_eq!(get_drop_args(&[], None), None);
+    }
+}
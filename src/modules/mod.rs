@@ -17,6 +17,8 @@ pub mod music;
 pub mod script;
 #[cfg(feature = "sys_info")]
 pub mod sysinfo;
+#[cfg(feature = "timers")]
+pub mod timers;
 #[cfg(feature = "tray")]
 pub mod tray;
 #[cfg(feature = "upower")]
@@ -293,3 +295,29 @@ pub fn wrap_widget<W: IsA<Widget>>(
 
     container
 }
+
+/// Converts a number of seconds into a short human-readable duration,
+/// such as `1d 2h 5m`.
+///
+/// Durations under a minute produce an empty string.
+#[cfg(any(feature = "timers", feature = "upower"))]
+pub fn seconds_to_string(seconds: i64) -> String {
+    const DAY: i64 = 24 * 60 * 60;
+    const HOUR: i64 = 60 * 60;
+    const MINUTE: i64 = 60;
+
+    let mut time_string = String::new();
+    let days = seconds / (DAY);
+    if days > 0 {
+        time_string += &format!("{days}d");
+    }
+    let hours = (seconds % DAY) / HOUR;
+    if hours > 0 {
+        time_string += &format!(" {hours}h");
+    }
+    let minutes = (seconds % HOUR) / MINUTE;
+    if minutes > 0 {
+        time_string += &format!(" {minutes}m");
+    }
+    time_string.trim_start().to_string()
+}
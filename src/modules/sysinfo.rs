@@ -227,7 +227,7 @@ impl Module<gtk::Box> for SysInfoModule {
     }
 }
 
-fn refresh_memory_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
+pub fn refresh_memory_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
     sys.refresh_memory();
 
     let total_memory = sys.total_memory();
@@ -274,7 +274,7 @@ fn refresh_memory_tokens(format_info: &mut HashMap<String, String>, sys: &mut Sy
     );
 }
 
-fn refresh_cpu_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
+pub fn refresh_cpu_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
     sys.refresh_cpu();
 
     let cpu_info = sys.global_cpu_info();
@@ -283,7 +283,7 @@ fn refresh_cpu_tokens(format_info: &mut HashMap<String, String>, sys: &mut Syste
     format_info.insert(String::from("cpu_percent"), format!("{cpu_percent:0>2.0}"));
 }
 
-fn refresh_temp_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
+pub fn refresh_temp_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
     sys.refresh_components();
 
     let components = sys.components();
@@ -296,7 +296,7 @@ fn refresh_temp_tokens(format_info: &mut HashMap<String, String>, sys: &mut Syst
     }
 }
 
-fn refresh_disk_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
+pub fn refresh_disk_tokens(format_info: &mut HashMap<String, String>, sys: &mut System) {
     sys.refresh_disks();
 
     for disk in sys.disks() {
@@ -334,7 +334,7 @@ fn refresh_disk_tokens(format_info: &mut HashMap<String, String>, sys: &mut Syst
     }
 }
 
-fn refresh_network_tokens(
+pub fn refresh_network_tokens(
     format_info: &mut HashMap<String, String>,
     sys: &mut System,
     interval: u64,
@@ -357,7 +357,7 @@ fn refresh_network_tokens(
     }
 }
 
-fn refresh_system_tokens(format_info: &mut HashMap<String, String>, sys: &System) {
+pub fn refresh_system_tokens(format_info: &mut HashMap<String, String>, sys: &System) {
     // no refresh required for these tokens
 
     let load_average = sys.load_average();
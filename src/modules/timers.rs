@@ -0,0 +1,412 @@
+use crate::config::CommonConfig;
+use crate::gtk_helpers::add_class;
+use crate::modules::{
+    seconds_to_string, Module, ModuleInfo, ModuleUpdateEvent, ModuleWidget, WidgetContext,
+};
+use crate::popup::Popup;
+use crate::{send_async, try_send};
+use chrono::{DateTime, Local, TimeZone};
+use color_eyre::eyre::WrapErr;
+use color_eyre::{Report, Result};
+use glib::markup_escape_text;
+use gtk::prelude::*;
+use gtk::{Align, Button, Label, Orientation};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::spawn;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::time::sleep;
+use tracing::error;
+
+#[derive(Debug, Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerScope {
+    /// Only shows timers from the user's service manager.
+    User,
+    /// Only shows timers from the system service manager.
+    System,
+    /// Shows timers from both service managers.
+    All,
+}
+
+impl Default for TimerScope {
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TimersModule {
+    /// Format string for the bar label,
+    /// using the soonest timer.
+    #[serde(default = "default_format")]
+    format: String,
+
+    /// `chrono` format string used for the `{next}` token
+    /// and the popup.
+    #[serde(default = "default_time_format")]
+    time_format: String,
+
+    /// Which service managers to list timers from.
+    #[serde(default)]
+    scope: TimerScope,
+
+    /// Number of seconds between refresh.
+    #[serde(default = "default_interval")]
+    interval: u64,
+
+    #[serde(flatten)]
+    pub common: Option<CommonConfig>,
+}
+
+fn default_format() -> String {
+    String::from("{unit} in {left}")
+}
+
+fn default_time_format() -> String {
+    String::from("%a %H:%M")
+}
+
+const fn default_interval() -> u64 {
+    30
+}
+
+/// A single timer entry
+/// as output by `systemctl list-timers --output=json`.
+///
+/// Times are given in microseconds since the epoch,
+/// or are missing/zero if not applicable.
+#[derive(Debug, Deserialize)]
+struct SystemctlTimer {
+    unit: String,
+    activates: Option<String>,
+    next: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Timer {
+    unit: String,
+    activates: String,
+    next: Option<DateTime<Local>>,
+    user: bool,
+}
+
+impl Timer {
+    /// Replaces the format tokens in `format`
+    /// with this timer's values.
+    ///
+    /// Values are escaped, as the output is used as Pango markup.
+    fn format(&self, format: &str, time_format: &str) -> String {
+        let next = self.next.map_or_else(
+            || String::from("n/a"),
+            |next| next.format(time_format).to_string(),
+        );
+
+        let left = self.next.map_or_else(
+            || String::from("n/a"),
+            |next| {
+                let left = seconds_to_string((next - Local::now()).num_seconds());
+                if left.is_empty() {
+                    String::from("<1m")
+                } else {
+                    left
+                }
+            },
+        );
+
+        format
+            .replace("{unit}", &markup_escape_text(&self.unit))
+            .replace("{activates}", &markup_escape_text(&self.activates))
+            .replace("{next}", &markup_escape_text(&next))
+            .replace("{left}", &markup_escape_text(&left))
+    }
+}
+
+/// Gets the list of timers from a service manager using `systemctl`.
+async fn get_timers(user: bool) -> Result<Vec<Timer>> {
+    let mut command = Command::new("systemctl");
+    if user {
+        command.arg("--user");
+    }
+
+    let output = command
+        .args(["list-timers", "--output=json"])
+        .output()
+        .await
+        .wrap_err("Failed to run systemctl")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Report::msg(stderr.trim().to_string()).wrap_err("Failed to list timers"));
+    }
+
+    let timers = serde_json::from_slice::<Vec<SystemctlTimer>>(&output.stdout)
+        .wrap_err("Failed to parse systemctl output")?;
+
+    Ok(parse_timers(timers, user))
+}
+
+/// Converts the timers output by `systemctl` into `Timer`s.
+///
+/// Missing or zero `next` times are treated as never elapsing.
+fn parse_timers(timers: Vec<SystemctlTimer>, user: bool) -> Vec<Timer> {
+    timers
+        .into_iter()
+        .map(|timer| Timer {
+            unit: timer.unit,
+            activates: timer.activates.unwrap_or_default(),
+            next: timer.next.filter(|&next| next > 0).and_then(|next| {
+                Local
+                    .timestamp_opt(next / 1_000_000, (next % 1_000_000) as u32 * 1000)
+                    .single()
+            }),
+            user,
+        })
+        .collect()
+}
+
+impl Module<Button> for TimersModule {
+    type SendMessage = Vec<Timer>;
+    type ReceiveMessage = ();
+
+    fn name() -> &'static str {
+        "timers"
+    }
+
+    fn spawn_controller(
+        &self,
+        _info: &ModuleInfo,
+        tx: Sender<ModuleUpdateEvent<Self::SendMessage>>,
+        _rx: Receiver<Self::ReceiveMessage>,
+    ) -> Result<()> {
+        let scope = self.scope;
+        let interval = self.interval;
+
+        spawn(async move {
+            loop {
+                let mut timers = vec![];
+
+                if matches!(scope, TimerScope::User | TimerScope::All) {
+                    match get_timers(true).await {
+                        Ok(user_timers) => timers.extend(user_timers),
+                        Err(err) => error!("{err:?}"),
+                    }
+                }
+
+                if matches!(scope, TimerScope::System | TimerScope::All) {
+                    match get_timers(false).await {
+                        Ok(system_timers) => timers.extend(system_timers),
+                        Err(err) => error!("{err:?}"),
+                    }
+                }
+
+                // soonest first, with timers that will not elapse at the end
+                timers.sort_by_key(|timer| (timer.next.is_none(), timer.next));
+
+                send_async!(tx, ModuleUpdateEvent::Update(timers));
+                sleep(Duration::from_secs(interval)).await;
+            }
+        });
+
+        Ok(())
+    }
+
+    fn into_widget(
+        self,
+        context: WidgetContext<Self::SendMessage, Self::ReceiveMessage>,
+        info: &ModuleInfo,
+    ) -> Result<ModuleWidget<Button>> {
+        let button = Button::new();
+        let label = Label::builder().use_markup(true).build();
+        label.set_angle(info.bar_position.get_angle());
+        button.add(&label);
+
+        let orientation = info.bar_position.get_orientation();
+        button.connect_clicked(move |button| {
+            try_send!(
+                context.tx,
+                ModuleUpdateEvent::TogglePopup(Popup::widget_geometry(button, orientation))
+            );
+        });
+
+        {
+            let format = self.format.clone();
+            let time_format = self.time_format.clone();
+
+            context.widget_rx.attach(None, move |timers| {
+                let text = timers
+                    .first()
+                    .filter(|timer| timer.next.is_some())
+                    .map_or_else(String::new, |timer| timer.format(&format, &time_format));
+
+                label.set_markup(&text);
+                Continue(true)
+            });
+        }
+
+        let popup = self.into_popup(context.controller_tx, context.popup_rx, info);
+
+        Ok(ModuleWidget {
+            widget: button,
+            popup,
+        })
+    }
+
+    fn into_popup(
+        self,
+        _tx: Sender<Self::ReceiveMessage>,
+        rx: glib::Receiver<Self::SendMessage>,
+        _info: &ModuleInfo,
+    ) -> Option<gtk::Box>
+    where
+        Self: Sized,
+    {
+        let container = gtk::Box::new(Orientation::Vertical, 5);
+
+        let time_format = self.time_format;
+
+        {
+            let container = container.clone();
+
+            rx.attach(None, move |timers| {
+                for child in container.children() {
+                    container.remove(&child);
+                }
+
+                for timer in timers {
+                    let row = gtk::Box::new(Orientation::Horizontal, 10);
+                    add_class(&row, "timer");
+                    add_class(&row, if timer.user { "user" } else { "system" });
+
+                    let unit_label = Label::builder()
+                        .label(&timer.unit)
+                        .halign(Align::Start)
+                        .hexpand(true)
+                        .build();
+                    add_class(&unit_label, "unit");
+
+                    let next_label = Label::builder()
+                        .label(&timer.format("{next}", &time_format))
+                        .use_markup(true)
+                        .halign(Align::End)
+                        .build();
+                    add_class(&next_label, "next");
+
+                    let left_label = Label::builder()
+                        .label(&timer.format("{left}", &time_format))
+                        .use_markup(true)
+                        .halign(Align::End)
+                        .build();
+                    add_class(&left_label, "left");
+
+                    row.add(&unit_label);
+                    row.add(&next_label);
+                    row.add(&left_label);
+
+                    container.add(&row);
+                }
+
+                container.show_all();
+                Continue(true)
+            });
+        }
+
+        container.show_all();
+
+        Some(container)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = r#"[
+        {
+            "next": 1689505200000000,
+            "left": 1689505200000000,
+            "last": 1689418800123456,
+            "passed": 1689418800123456,
+            "unit": "logrotate.timer",
+            "activates": "logrotate.service"
+        },
+        {
+            "next": 1689505200500000,
+            "left": 1689505200500000,
+            "last": null,
+            "passed": null,
+            "unit": "backup.timer",
+            "activates": null
+        },
+        {
+            "next": null,
+            "left": null,
+            "last": null,
+            "passed": null,
+            "unit": "never.timer",
+            "activates": "never.service"
+        },
+        {
+            "next": 0,
+            "left": 0,
+            "last": 0,
+            "passed": 0,
+            "unit": "zero.timer",
+            "activates": "zero.service"
+        }
+    ]"#;
+
+    fn parse() -> Vec<Timer> {
+        let timers = serde_json::from_str::<Vec<SystemctlTimer>>(OUTPUT).unwrap();
+        parse_timers(timers, true)
+    }
+
+    #[test]
+    fn test_parse_timers() {
+        let timers = parse();
+
+        assert_eq!(timers.len(), 4);
+        assert!(timers.iter().all(|timer| timer.user));
+
+        assert_eq!(timers[0].unit, "logrotate.timer");
+        assert_eq!(timers[0].activates, "logrotate.service");
+        assert_eq!(
+            timers[0].next.map(|next| next.timestamp()),
+            Some(1_689_505_200)
+        );
+    }
+
+    #[test]
+    fn test_parse_timers_micros() {
+        let timers = parse();
+
+        let next = timers[1].next.unwrap();
+        assert_eq!(next.timestamp(), 1_689_505_200);
+        assert_eq!(next.timestamp_subsec_micros(), 500_000);
+        assert_eq!(timers[1].activates, "");
+    }
+
+    #[test]
+    fn test_parse_timers_never() {
+        let timers = parse();
+
+        assert_eq!(timers[2].next, None);
+        assert_eq!(timers[3].next, None);
+    }
+
+    #[test]
+    fn test_format_escapes() {
+        let timer = Timer {
+            unit: String::from("a&b<c>.timer"),
+            activates: String::from("a&b.service"),
+            next: None,
+            user: false,
+        };
+
+        assert_eq!(
+            timer.format("<b>{unit}</b> {activates} {next} {left}", "%H:%M"),
+            "<b>a&amp;b&lt;c&gt;.timer</b> a&amp;b.service n/a n/a"
+        );
+    }
+}
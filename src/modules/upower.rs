@@ -2,7 +2,9 @@ use crate::clients::upower::get_display_proxy;
 use crate::config::CommonConfig;
 use crate::gtk_helpers::add_class;
 use crate::image::ImageProvider;
-use crate::modules::{Module, ModuleInfo, ModuleUpdateEvent, ModuleWidget, WidgetContext};
+use crate::modules::{
+    seconds_to_string, Module, ModuleInfo, ModuleUpdateEvent, ModuleWidget, WidgetContext,
+};
 use crate::popup::Popup;
 use crate::{await_sync, error, send_async, try_send};
 use color_eyre::Result;
@@ -15,10 +17,6 @@ use tokio::sync::mpsc::{Receiver, Sender};
 use upower_dbus::BatteryState;
 use zbus;
 
-const DAY: i64 = 24 * 60 * 60;
-const HOUR: i64 = 60 * 60;
-const MINUTE: i64 = 60;
-
 #[derive(Debug, Deserialize, Clone)]
 pub struct UpowerModule {
     #[serde(default = "default_format")]
@@ -254,23 +252,6 @@ impl Module<gtk::Button> for UpowerModule {
     }
 }
 
-fn seconds_to_string(seconds: i64) -> String {
-    let mut time_string = String::new();
-    let days = seconds / (DAY);
-    if days > 0 {
-        time_string += &format!("{days}d");
-    }
-    let hours = (seconds % DAY) / HOUR;
-    if hours > 0 {
-        time_string += &format!(" {hours}h");
-    }
-    let minutes = (seconds % HOUR) / MINUTE;
-    if minutes > 0 {
-        time_string += &format!(" {minutes}m");
-    }
-    time_string.trim_start().to_string()
-}
-
 const fn u32_to_battery_state(number: u32) -> Result<BatteryState, u32> {
     if number == (BatteryState::Unknown as u32) {
         Ok(BatteryState::Unknown)
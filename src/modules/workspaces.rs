@@ -2,16 +2,19 @@ use crate::clients::compositor::{Compositor, WorkspaceUpdate};
 use crate::config::CommonConfig;
 use crate::image::new_icon_button;
 use crate::modules::{Module, ModuleInfo, ModuleUpdateEvent, ModuleWidget, WidgetContext};
+use crate::script::{OutputStream, Script};
 use crate::{send_async, try_send};
 use color_eyre::{Report, Result};
 use gtk::prelude::*;
 use gtk::{Button, IconTheme};
 use serde::Deserialize;
+use std::cell::Cell;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::rc::Rc;
 use tokio::spawn;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tracing::trace;
+use tracing::{error, trace};
 
 #[derive(Debug, Deserialize, Clone, Copy, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -29,6 +32,62 @@ impl Default for SortOrder {
     }
 }
 
+/// An action to run against a workspace
+/// when its button is clicked.
+#[derive(Debug, Deserialize, Clone, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceAction {
+    /// Focuses the workspace.
+    Focus,
+    /// Moves the currently focused window to the workspace.
+    MoveFocused,
+    /// Renames the workspace.
+    /// The script is run and its output is used as the new name.
+    Rename(String),
+    /// Closes all windows on the workspace.
+    KillAll,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorkspaceActions {
+    #[serde(default = "default_left_action")]
+    left: Option<WorkspaceAction>,
+    middle: Option<WorkspaceAction>,
+    right: Option<WorkspaceAction>,
+}
+
+impl Default for WorkspaceActions {
+    fn default() -> Self {
+        Self {
+            left: default_left_action(),
+            middle: None,
+            right: None,
+        }
+    }
+}
+
+impl WorkspaceActions {
+    /// Gets the action bound to the GDK mouse button number, if any.
+    fn get(&self, button: u32) -> Option<&WorkspaceAction> {
+        match button {
+            1 => self.left.as_ref(),
+            2 => self.middle.as_ref(),
+            3 => self.right.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+const fn default_left_action() -> Option<WorkspaceAction> {
+    Some(WorkspaceAction::Focus)
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceActionEvent {
+    name: String,
+    action: WorkspaceAction,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct WorkspacesModule {
     /// Map of actual workspace names to custom names.
@@ -44,6 +103,10 @@ pub struct WorkspacesModule {
     #[serde(default = "default_icon_size")]
     icon_size: i32,
 
+    /// Actions to run when workspace buttons are clicked.
+    #[serde(default)]
+    actions: WorkspaceActions,
+
     #[serde(flatten)]
     pub common: Option<CommonConfig>,
 }
@@ -59,7 +122,8 @@ fn create_button(
     name_map: &HashMap<String, String>,
     icon_theme: &IconTheme,
     icon_size: i32,
-    tx: &Sender<String>,
+    actions: &WorkspaceActions,
+    tx: &Sender<WorkspaceActionEvent>,
 ) -> Button {
     let label = name_map.get(name).map_or(name, String::as_str);
 
@@ -73,11 +137,59 @@ fn create_button(
         style_context.add_class("focused");
     }
 
-    {
+    let send_action = {
         let tx = tx.clone();
         let name = name.to_string();
-        button.connect_clicked(move |_item| {
-            try_send!(tx, name.clone());
+
+        move |action: &WorkspaceAction| {
+            try_send!(
+                tx,
+                WorkspaceActionEvent {
+                    name: name.clone(),
+                    action: action.clone(),
+                }
+            );
+        }
+    };
+
+    // left click uses `clicked` so keyboard activation works
+    if let Some(action) = actions.left.clone() {
+        let send_action = send_action.clone();
+        button.connect_clicked(move |_item| send_action(&action));
+    }
+
+    // other buttons must be both pressed and released on the button,
+    // so releasing a drag that started elsewhere does not trigger an action
+    let pressed = Rc::new(Cell::new(None));
+
+    {
+        let pressed = pressed.clone();
+        button.connect_button_press_event(move |_item, event| {
+            pressed.set(Some(event.button()));
+            Inhibit(false)
+        });
+    }
+
+    {
+        let actions = actions.clone();
+
+        button.connect_button_release_event(move |item, event| {
+            let was_pressed = pressed.take() == Some(event.button());
+
+            let (x, y) = event.position();
+            let allocation = item.allocation();
+            let is_inside = x >= 0.0
+                && y >= 0.0
+                && x < f64::from(allocation.width())
+                && y < f64::from(allocation.height());
+
+            if event.button() != 1 && was_pressed && is_inside {
+                if let Some(action) = actions.get(event.button()) {
+                    send_action(action);
+                }
+            }
+
+            Inhibit(false)
         });
     }
 
@@ -105,9 +217,48 @@ fn reorder_workspaces(container: &gtk::Box) {
     }
 }
 
+/// Runs a workspace action against the named workspace
+/// using the current compositor's workspace client.
+async fn run_action(name: String, action: WorkspaceAction) -> Result<()> {
+    // the client cannot be held across an await,
+    // so any script output must be fetched first.
+    let new_name = match &action {
+        WorkspaceAction::Rename(cmd) => get_new_name(cmd, &name).await?,
+        _ => None,
+    };
+
+    let client = Compositor::get_workspace_client()?;
+
+    match action {
+        WorkspaceAction::Focus => client.focus(name),
+        WorkspaceAction::MoveFocused => client.move_focused(name),
+        WorkspaceAction::Rename(_) => {
+            new_name.map_or(Ok(()), |new_name| client.rename(name, new_name))
+        }
+        WorkspaceAction::KillAll => client.kill_all(name),
+    }
+}
+
+/// Runs the rename script for a workspace,
+/// passing the current name as an argument.
+///
+/// Returns `None` if the script produced no output.
+async fn get_new_name(cmd: &str, name: &str) -> Result<Option<String>> {
+    let script = Script::from(cmd);
+
+    match script.get_output(Some(&[name.to_string()])).await? {
+        (OutputStream::Stdout(new_name), true) => {
+            Ok(Some(new_name).filter(|new_name| !new_name.is_empty()))
+        }
+        (OutputStream::Stderr(err) | OutputStream::Stdout(err), _) => {
+            Err(Report::msg(err).wrap_err("Rename script failed"))
+        }
+    }
+}
+
 impl Module<gtk::Box> for WorkspacesModule {
     type SendMessage = WorkspaceUpdate;
-    type ReceiveMessage = String;
+    type ReceiveMessage = WorkspaceActionEvent;
 
     fn name() -> &'static str {
         "workspaces"
@@ -134,17 +285,15 @@ impl Module<gtk::Box> for WorkspacesModule {
             }
         });
 
-        // Change workspace focus
+        // Run workspace actions
         spawn(async move {
             trace!("Setting up UI event handler");
 
-            while let Some(name) = rx.recv().await {
-                let client =
-                    Compositor::get_workspace_client().expect("Failed to get workspace client");
-                client.focus(name)?;
+            while let Some(WorkspaceActionEvent { name, action }) = rx.recv().await {
+                if let Err(err) = run_action(name, action).await {
+                    error!("{err:?}");
+                }
             }
-
-            Ok::<(), Report>(())
         });
 
         Ok(())
@@ -184,6 +333,7 @@ impl Module<gtk::Box> for WorkspacesModule {
                                         &name_map,
                                         &icon_theme,
                                         icon_size,
+                                        &self.actions,
                                         &context.controller_tx,
                                     );
                                     container.add(&item);
@@ -220,6 +370,7 @@ impl Module<gtk::Box> for WorkspacesModule {
                                 &name_map,
                                 &icon_theme,
                                 icon_size,
+                                &self.actions,
                                 &context.controller_tx,
                             );
 
@@ -245,6 +396,7 @@ impl Module<gtk::Box> for WorkspacesModule {
                                     &name_map,
                                     &icon_theme,
                                     icon_size,
+                                    &self.actions,
                                     &context.controller_tx,
                                 );
 
@@ -283,3 +435,40 @@ impl Module<gtk::Box> for WorkspacesModule {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_default() {
+        let actions: WorkspaceActions = serde_json::from_str("{}").unwrap();
+
+        assert_eq!(actions.left, Some(WorkspaceAction::Focus));
+        assert_eq!(actions.middle, None);
+        assert_eq!(actions.right, None);
+    }
+
+    #[test]
+    fn test_actions_null() {
+        let actions: WorkspaceActions =
+            serde_json::from_str(r#"{ "left": null, "middle": null }"#).unwrap();
+
+        assert_eq!(actions.left, None);
+        assert_eq!(actions.middle, None);
+        assert_eq!(actions.right, None);
+    }
+
+    #[test]
+    fn test_actions_rename() {
+        let actions: WorkspaceActions =
+            serde_json::from_str(r#"{ "right": { "rename": "zenity --entry" } }"#).unwrap();
+
+        assert_eq!(actions.left, Some(WorkspaceAction::Focus));
+        assert_eq!(
+            actions.right,
+            Some(WorkspaceAction::Rename(String::from("zenity --entry")))
+        );
+        assert_eq!(actions.get(3), actions.right.as_ref());
+    }
+}
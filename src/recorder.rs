@@ -0,0 +1,679 @@
+use crate::modules::sysinfo::{
+    refresh_cpu_tokens, refresh_disk_tokens, refresh_memory_tokens, refresh_network_tokens,
+    refresh_system_tokens, refresh_temp_tokens,
+};
+use crate::{arc_rw, lock, write_lock};
+use color_eyre::eyre::WrapErr;
+use color_eyre::Result;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{RefreshKind, System, SystemExt};
+use tokio::spawn;
+use tokio::task::{spawn_blocking, JoinHandle};
+use tokio::time::sleep;
+use tracing::{debug, error, info};
+
+lazy_static! {
+    static ref RECORDER: Arc<RwLock<Recorder>> = arc_rw!(Recorder::new());
+}
+
+/// Identifies the current recording run.
+/// This is incremented each time the recorder is stopped,
+/// so that tasks from previous runs can detect they are stale.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Held while writing to disk,
+/// so that writes from a stale run cannot interleave with a new one.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Checks whether `generation` is the current recording run.
+fn is_current(generation: u64) -> bool {
+    GENERATION.load(Ordering::SeqCst) == generation
+}
+
+pub fn get_recorder() -> Arc<RwLock<Recorder>> {
+    RECORDER.clone()
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// Comma-separated values, with a header row.
+    Csv,
+    /// One JSON object per line.
+    Json,
+}
+
+impl Default for RecordFormat {
+    fn default() -> Self {
+        Self::Csv
+    }
+}
+
+impl RecordFormat {
+    const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RecorderConfig {
+    /// List of value keys to record.
+    /// These use the same names as the `sys_info` module tokens,
+    /// plus `battery_percent`.
+    #[serde(default)]
+    values: Vec<String>,
+
+    /// Number of seconds between samples.
+    #[serde(default = "default_interval")]
+    interval: u64,
+
+    #[serde(default)]
+    format: RecordFormat,
+
+    /// Directory to write sample files to.
+    /// Defaults to `~/.local/share/ironbar/recordings`.
+    path: Option<PathBuf>,
+
+    /// Size in kilobytes at which the current file is rotated.
+    #[serde(default = "default_max_file_size")]
+    max_file_size: u64,
+
+    /// Number of files to keep, including the current file.
+    #[serde(default = "default_max_files")]
+    max_files: usize,
+
+    /// Number of recent samples to keep in memory for IPC queries.
+    #[serde(default = "default_history")]
+    history: usize,
+}
+
+const fn default_interval() -> u64 {
+    5
+}
+
+const fn default_max_file_size() -> u64 {
+    1024
+}
+
+const fn default_max_files() -> usize {
+    5
+}
+
+const fn default_history() -> usize {
+    120
+}
+
+/// A single recorded sample.
+/// Values are stored in the same order as the configured keys.
+#[derive(Debug, Clone)]
+struct Sample {
+    timestamp: u64,
+    values: Vec<Option<String>>,
+}
+
+impl Sample {
+    /// Converts the sample into a JSON object,
+    /// using numbers where values can be parsed as such.
+    fn to_json(&self, keys: &[String]) -> Value {
+        let mut map = Map::new();
+        map.insert(String::from("timestamp"), Value::from(self.timestamp));
+
+        for (key, value) in keys.iter().zip(&self.values) {
+            let value = value.as_ref().map_or(Value::Null, |value| {
+                value
+                    .parse::<f64>()
+                    .map_or_else(|_| Value::from(value.as_str()), Value::from)
+            });
+
+            map.insert(key.clone(), value);
+        }
+
+        Value::Object(map)
+    }
+
+    /// Converts the sample into a CSV row.
+    fn to_csv(&self) -> String {
+        let values = self
+            .values
+            .iter()
+            .map(|value| value.as_deref().map(csv_escape).unwrap_or_default());
+
+        std::iter::once(self.timestamp.to_string())
+            .chain(values)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Global singleton which samples configured values
+/// and keeps a history of recent samples.
+pub struct Recorder {
+    keys: Vec<String>,
+    samples: VecDeque<Sample>,
+    history: usize,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        Self {
+            keys: vec![],
+            samples: VecDeque::new(),
+            history: 0,
+            handle: None,
+        }
+    }
+
+    /// Starts recording using the provided config.
+    /// Any existing recording is stopped first.
+    pub fn start(&mut self, mut config: RecorderConfig) {
+        self.stop();
+
+        info!("Starting recorder for {} values", config.values.len());
+
+        // an interval of zero would sample in a busy loop
+        config.interval = config.interval.max(1);
+
+        self.keys = config.values.clone();
+        self.history = config.history;
+
+        let generation = GENERATION.load(Ordering::SeqCst);
+
+        let writer = Arc::new(RecordWriter::new(&config));
+        let handle = spawn(async move {
+            let keys = Arc::new(config.values);
+
+            {
+                let writer = writer.clone();
+                let keys = keys.clone();
+                if let Err(err) = run_blocking(generation, move || writer.prepare(&keys)).await {
+                    error!("{err:?}");
+                }
+            }
+
+            let mut sampler = Sampler::new(config.interval);
+
+            loop {
+                let tokens = sampler.sample().await;
+                let sample = Sample {
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs())
+                        .unwrap_or_default(),
+                    values: keys.iter().map(|key| tokens.get(key).cloned()).collect(),
+                };
+
+                {
+                    let writer = writer.clone();
+                    let keys = keys.clone();
+                    let sample = sample.clone();
+                    if let Err(err) =
+                        run_blocking(generation, move || writer.write(&sample, &keys)).await
+                    {
+                        error!("{err:?}");
+                    }
+                }
+
+                {
+                    // aborting only takes effect at the next await,
+                    // so the run may have been stopped since sampling
+                    let mut recorder = write_lock!(RECORDER);
+                    if !is_current(generation) {
+                        break;
+                    }
+
+                    recorder.push(sample);
+                }
+
+                sleep(Duration::from_secs(config.interval)).await;
+            }
+        });
+
+        self.handle = Some(handle);
+    }
+
+    /// Stops recording, if running.
+    /// The in-memory history is cleared.
+    pub fn stop(&mut self) {
+        GENERATION.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(handle) = self.handle.take() {
+            debug!("Stopping recorder");
+            handle.abort();
+        }
+
+        self.samples.clear();
+    }
+
+    /// Adds a new sample to the history,
+    /// dropping the oldest sample if it is full.
+    fn push(&mut self, sample: Sample) {
+        if self.samples.len() >= self.history {
+            self.samples.pop_front();
+        }
+
+        if self.history > 0 {
+            self.samples.push_back(sample);
+        }
+    }
+
+    /// Gets the most recent samples as a JSON array, oldest first.
+    /// If `limit` is provided, at most that many samples are returned.
+    pub fn recent_samples(&self, limit: Option<usize>) -> Value {
+        let limit = limit.unwrap_or(self.samples.len());
+
+        let samples = self
+            .samples
+            .iter()
+            .skip(self.samples.len().saturating_sub(limit))
+            .map(|sample| sample.to_json(&self.keys))
+            .collect();
+
+        Value::Array(samples)
+    }
+}
+
+/// Collects the current value for every supported key.
+struct Sampler {
+    sys: System,
+    interval: u64,
+}
+
+impl Sampler {
+    fn new(interval: u64) -> Self {
+        let refresh_kind = RefreshKind::everything()
+            .without_processes()
+            .without_users_list();
+
+        let mut sys = System::new_with_specifics(refresh_kind);
+        sys.refresh_components_list();
+        sys.refresh_disks_list();
+        sys.refresh_networks_list();
+
+        Self { sys, interval }
+    }
+
+    async fn sample(&mut self) -> HashMap<String, String> {
+        let mut tokens = HashMap::new();
+
+        refresh_memory_tokens(&mut tokens, &mut self.sys);
+        refresh_cpu_tokens(&mut tokens, &mut self.sys);
+        refresh_temp_tokens(&mut tokens, &mut self.sys);
+        refresh_disk_tokens(&mut tokens, &mut self.sys);
+        refresh_network_tokens(&mut tokens, &mut self.sys, self.interval);
+        refresh_system_tokens(&mut tokens, &self.sys);
+
+        #[cfg(feature = "upower")]
+        match get_battery_percent().await {
+            Ok(percentage) => {
+                tokens.insert(String::from("battery_percent"), format!("{percentage:.0}"));
+            }
+            Err(err) => debug!("Unable to sample battery: {err:?}"),
+        }
+
+        tokens
+    }
+}
+
+/// Runs a blocking filesystem operation
+/// on a separate thread.
+///
+/// The operation is skipped if the recording run
+/// identified by `generation` has since been stopped.
+async fn run_blocking<F>(generation: u64, f: F) -> Result<()>
+where
+    F: FnOnce() -> Result<()> + Send + 'static,
+{
+    spawn_blocking(move || {
+        let _lock = lock!(WRITE_LOCK);
+
+        if is_current(generation) {
+            f()
+        } else {
+            debug!("Skipping write from stopped recorder");
+            Ok(())
+        }
+    })
+    .await?
+}
+
+/// Gets the current battery percentage from the UPower display device.
+#[cfg(feature = "upower")]
+async fn get_battery_percent() -> Result<f64> {
+    use crate::await_sync;
+    use crate::clients::upower::get_display_proxy;
+    use color_eyre::Report;
+
+    // await_sync due to strange "higher-ranked lifetime error"
+    let display_proxy = await_sync(async move { get_display_proxy().await });
+
+    let device_interface_name =
+        zbus::names::InterfaceName::from_static_str("org.freedesktop.UPower.Device")?;
+
+    let percentage = display_proxy
+        .get(device_interface_name, "Percentage")
+        .await?;
+
+    percentage
+        .downcast_ref::<f64>()
+        .copied()
+        .ok_or_else(|| Report::msg("expected Percentage to be f64"))
+}
+
+/// Writes samples to disk,
+/// rotating files once they exceed the maximum size.
+struct RecordWriter {
+    dir: PathBuf,
+    format: RecordFormat,
+    max_file_size: u64,
+    max_files: usize,
+}
+
+impl RecordWriter {
+    fn new(config: &RecorderConfig) -> Self {
+        let dir = config.path.clone().unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_default()
+                .join("ironbar")
+                .join("recordings")
+        });
+
+        Self {
+            dir,
+            format: config.format,
+            max_file_size: config.max_file_size * 1024,
+            max_files: config.max_files.max(1),
+        }
+    }
+
+    /// Gets the path of the file at `index`,
+    /// where `0` is the current file
+    /// and higher numbers are older.
+    fn file_path(&self, index: usize) -> PathBuf {
+        let extension = self.format.extension();
+
+        if index == 0 {
+            self.dir.join(format!("samples.{extension}"))
+        } else {
+            self.dir.join(format!("samples.{index}.{extension}"))
+        }
+    }
+
+    /// Shifts each file back by one,
+    /// removing the oldest if the maximum is reached.
+    fn rotate(&self) -> Result<()> {
+        debug!("Rotating recorder files in {}", self.dir.display());
+
+        for index in (1..self.max_files).rev() {
+            let from = self.file_path(index - 1);
+            if from.exists() {
+                fs::rename(&from, self.file_path(index))?;
+            }
+        }
+
+        if self.max_files == 1 {
+            fs::remove_file(self.file_path(0))?;
+        }
+
+        Ok(())
+    }
+
+    /// Prepares the current file for writing with `keys`.
+    ///
+    /// If the current CSV file was written with a different set of keys,
+    /// it is rotated so that a new file with the correct header is started.
+    fn prepare(&self, keys: &[String]) -> Result<()> {
+        if self.format != RecordFormat::Csv {
+            return Ok(());
+        }
+
+        let path = self.file_path(0);
+        if !path.exists() {
+            return Ok(());
+        }
+
+        let file = File::open(&path).wrap_err("Failed to open recorder file")?;
+
+        let mut header = String::new();
+        BufReader::new(file).read_line(&mut header)?;
+
+        if header.trim_end_matches(['\r', '\n']) != csv_header(keys) {
+            debug!("Recorder values changed, starting new file");
+            self.rotate().wrap_err("Failed to rotate recorder files")?;
+        }
+
+        Ok(())
+    }
+
+    fn write(&self, sample: &Sample, keys: &[String]) -> Result<()> {
+        fs::create_dir_all(&self.dir).wrap_err("Failed to create recorder directory")?;
+
+        let path = self.file_path(0);
+
+        let size = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        if size >= self.max_file_size {
+            self.rotate().wrap_err("Failed to rotate recorder files")?;
+        }
+
+        let is_new = !path.exists();
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .wrap_err("Failed to open recorder file")?;
+
+        match self.format {
+            RecordFormat::Csv => {
+                if is_new {
+                    writeln!(file, "{}", csv_header(keys))?;
+                }
+
+                writeln!(file, "{}", sample.to_csv())?;
+            }
+            RecordFormat::Json => {
+                writeln!(file, "{}", sample.to_json(keys))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the CSV header row for `keys`.
+fn csv_header(keys: &[String]) -> String {
+    std::iter::once("timestamp")
+        .chain(keys.iter().map(String::as_str))
+        .map(csv_escape)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Quotes a CSV field if it contains
+/// any characters which require it.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<String> {
+        vec![String::from("cpu_percent"), String::from("label")]
+    }
+
+    fn sample(timestamp: u64) -> Sample {
+        Sample {
+            timestamp,
+            values: vec![Some(String::from("12.5")), None],
+        }
+    }
+
+    /// Creates a writer for a new, empty directory in the system temp dir.
+    fn writer(name: &str, max_files: usize) -> RecordWriter {
+        let dir = std::env::temp_dir().join(format!("ironbar-recorder-{name}"));
+        fs::remove_dir_all(&dir).ok();
+
+        RecordWriter {
+            dir,
+            format: RecordFormat::Csv,
+            max_file_size: 1024,
+            max_files,
+        }
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_escape("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn test_sample_to_csv() {
+        assert_eq!(sample(10).to_csv(), "10,12.5,");
+    }
+
+    #[test]
+    fn test_sample_to_json() {
+        let mut sample = sample(10);
+        sample.values[1] = Some(String::from("hello"));
+
+        assert_eq!(
+            sample.to_json(&keys()),
+            serde_json::json!({ "timestamp": 10, "cpu_percent": 12.5, "label": "hello" })
+        );
+
+        sample.values[1] = None;
+        assert_eq!(sample.to_json(&keys())["label"], Value::Null);
+    }
+
+    #[test]
+    fn test_push_history() {
+        let mut recorder = Recorder::new();
+        recorder.keys = keys();
+        recorder.history = 2;
+
+        for timestamp in 0..3 {
+            recorder.push(sample(timestamp));
+        }
+
+        let timestamps = |samples: Value| {
+            samples
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|sample| sample["timestamp"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(timestamps(recorder.recent_samples(None)), vec![1, 2]);
+        assert_eq!(timestamps(recorder.recent_samples(Some(1))), vec![2]);
+        assert_eq!(timestamps(recorder.recent_samples(Some(5))), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_push_no_history() {
+        let mut recorder = Recorder::new();
+        recorder.push(sample(0));
+
+        assert_eq!(recorder.recent_samples(None), Value::Array(vec![]));
+    }
+
+    #[test]
+    fn test_rotate() {
+        let writer = writer("rotate", 3);
+        fs::create_dir_all(&writer.dir).unwrap();
+
+        for index in 0..3 {
+            fs::write(writer.file_path(index), index.to_string()).unwrap();
+        }
+
+        writer.rotate().unwrap();
+
+        assert!(!writer.file_path(0).exists());
+        assert_eq!(fs::read_to_string(writer.file_path(1)).unwrap(), "0");
+        assert_eq!(fs::read_to_string(writer.file_path(2)).unwrap(), "1");
+
+        fs::remove_dir_all(&writer.dir).ok();
+    }
+
+    #[test]
+    fn test_prepare_rotates_on_new_keys() {
+        let writer = writer("prepare", 2);
+
+        writer.write(&sample(0), &keys()).unwrap();
+        writer.prepare(&keys()).unwrap();
+        assert!(!writer.file_path(1).exists());
+
+        let new_keys = vec![String::from("memory_percent")];
+        writer.prepare(&new_keys).unwrap();
+        writer
+            .write(
+                &Sample {
+                    timestamp: 1,
+                    values: vec![Some(String::from("40"))],
+                },
+                &new_keys,
+            )
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(writer.file_path(0)).unwrap(),
+            "timestamp,memory_percent\n1,40\n"
+        );
+        assert_eq!(
+            fs::read_to_string(writer.file_path(1)).unwrap(),
+            "timestamp,cpu_percent,label\n0,12.5,\n"
+        );
+
+        fs::remove_dir_all(&writer.dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_stale_run_skips_write() {
+        let writer = Arc::new(writer("stale", 2));
+
+        let generation = GENERATION.load(Ordering::SeqCst);
+        Recorder::new().stop();
+        assert!(!is_current(generation));
+
+        {
+            let writer = writer.clone();
+            run_blocking(generation, move || writer.write(&sample(0), &keys()))
+                .await
+                .unwrap();
+        }
+        assert!(!writer.file_path(0).exists());
+
+        let generation = GENERATION.load(Ordering::SeqCst);
+        {
+            let writer = writer.clone();
+            run_blocking(generation, move || writer.write(&sample(0), &keys()))
+                .await
+                .unwrap();
+        }
+        assert!(writer.file_path(0).exists());
+
+        fs::remove_dir_all(&writer.dir).ok();
+    }
+}